@@ -0,0 +1,116 @@
+//! Rescale a migrated ledger to an operator-chosen total supply at genesis, preserving relative balances.
+
+use anyhow::Context;
+use libra_types::legacy_types::{legacy_address::LegacyAddress, legacy_recovery::LegacyRecovery};
+
+use crate::supply::{get_supply_struct, Supply};
+
+/// Knobs for a genesis supply rescale.
+#[derive(Debug, Clone)]
+pub struct SupplySettings {
+  /// The total the migrated ledger should sum to after rescaling.
+  pub target_future_supply: u64,
+  /// Reserved for a future rescale policy that forces a specific
+  /// locked/unlocked split instead of preserving the legacy one.
+  pub target_slow_locked_pct: Option<f64>,
+  /// Account to route the accumulated rounding remainder to, so the
+  /// post-scale sum equals `target_future_supply` exactly. Defaults to the
+  /// first account with a balance when not set.
+  pub remainder_account: Option<LegacyAddress>,
+}
+
+/// Rescale every account balance in `rec` so the ledger sums to
+/// `settings.target_future_supply`, preserving each account's relative
+/// share of the legacy supply (scaling every balance and every
+/// slow-wallet `unlocked` amount by the same factor `k = target / total`
+/// keeps the locked/unlocked split intact). Returns the mutated recovery
+/// vector and a freshly computed `Supply` so callers can re-run
+/// reconciliation.
+pub fn rescale_supply(
+  mut rec: Vec<LegacyRecovery>,
+  settings: &SupplySettings,
+) -> anyhow::Result<(Vec<LegacyRecovery>, Supply)> {
+  if settings.target_slow_locked_pct.is_some() {
+    anyhow::bail!(
+      "target_slow_locked_pct is not yet implemented; rescale_supply only preserves the legacy locked/unlocked split"
+    );
+  }
+
+  let supply = get_supply_struct(&rec)?;
+  if supply.total == 0 {
+    anyhow::bail!("cannot rescale a ledger with zero total supply");
+  }
+
+  let target = settings.target_future_supply;
+  let total = supply.total;
+
+  let mut distributed: u64 = 0;
+  let mut remainder_idx: Option<usize> = None;
+
+  for (i, r) in rec.iter_mut().enumerate() {
+    if r.balance.is_none() {
+      continue;
+    }
+
+    if let Some(sl) = r.slow_wallet.as_mut() {
+      sl.unlocked = scale_amount(sl.unlocked, target, total);
+    }
+
+    let b = r.balance.as_mut().unwrap();
+    b.coin = scale_amount(b.coin, target, total);
+    distributed = distributed
+      .checked_add(b.coin)
+      .context("rescaled supply overflowed while distributing")?;
+
+    if settings.remainder_account == r.account {
+      remainder_idx = Some(i);
+    } else if remainder_idx.is_none() && settings.remainder_account.is_none() {
+      remainder_idx = Some(i);
+    }
+  }
+
+  let remainder_idx =
+    remainder_idx.context("no account found to route the rounding remainder to")?;
+
+  // floor rounding on every account means the distributed sum is always
+  // <= target; route the shortfall to the designated account so the
+  // post-scale total lands on target exactly.
+  let remainder = target
+    .checked_sub(distributed)
+    .context("rescaled supply overshot target before remainder routing")?;
+  rec[remainder_idx].balance.as_mut().unwrap().coin = rec[remainder_idx]
+    .balance
+    .as_ref()
+    .unwrap()
+    .coin
+    .checked_add(remainder)
+    .context("routing rounding remainder overflowed the designated account's balance")?;
+
+  let rescaled_supply = get_supply_struct(&rec)?;
+  Ok((rec, rescaled_supply))
+}
+
+/// `amount * target / total`, using u128 intermediate precision and floor
+/// rounding so no single account's scaled balance can exceed its fair
+/// share.
+fn scale_amount(amount: u64, target: u64, total: u64) -> u64 {
+  ((amount as u128 * target as u128) / total as u128) as u64
+}
+
+#[test]
+fn test_rescale_hits_target_exactly() {
+  use std::path::PathBuf;
+
+  let p = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests/fixtures/sample_export_recovery.json");
+  let rec = crate::parse_json::parse(p).unwrap();
+
+  let settings = SupplySettings {
+    target_future_supply: 100_000_000_000,
+    target_slow_locked_pct: None,
+    remainder_account: None,
+  };
+
+  let (_rescaled, supply) = rescale_supply(rec, &settings).unwrap();
+  assert_eq!(supply.total, settings.target_future_supply);
+}
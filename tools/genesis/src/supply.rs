@@ -4,9 +4,10 @@ use libra_types::legacy_types::{
   legacy_recovery::LegacyRecovery,
 };
 use std::path::PathBuf;
-use anyhow::Context;
+use anyhow::{bail, Context};
+use serde::Serialize;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Supply {
   pub total: u64,
   pub normal: u64,
@@ -20,6 +21,7 @@ pub struct Supply {
 
 
 fn inc_supply(mut acc: Supply, r: &LegacyRecovery, dd_wallet_list: &Vec<LegacyAddress>) -> anyhow::Result<Supply> {
+    let account = r.account.context("recovery record missing account address")?;
 
     // get balances
     let amount: u64 = match &r.balance {
@@ -28,22 +30,29 @@ fn inc_supply(mut acc: Supply, r: &LegacyRecovery, dd_wallet_list: &Vec<LegacyAd
         },
         None => 0,
     };
-    acc.total = acc.total.checked_add(amount).unwrap();
+    acc.total = acc.total.checked_add(amount)
+      .with_context(|| format!("supply total overflowed at account {}", account))?;
 
     // get donor directed
-    if dd_wallet_list.contains(&r.account.unwrap()) {
-      acc.donor_directed = acc.donor_directed.checked_add(amount).unwrap();
+    if dd_wallet_list.contains(&account) {
+      acc.donor_directed = acc.donor_directed.checked_add(amount)
+        .with_context(|| format!("donor_directed overflowed at account {}", account))?;
     } else if let Some(sl) = &r.slow_wallet {
-      acc.slow_total = acc.slow_total.checked_add(amount).unwrap();
+      acc.slow_total = acc.slow_total.checked_add(amount)
+        .with_context(|| format!("slow_total overflowed at account {}", account))?;
       if sl.unlocked > 0 {
-        acc.slow_unlocked = acc.slow_unlocked.checked_add(amount).unwrap();
+        acc.slow_unlocked = acc.slow_unlocked.checked_add(amount)
+          .with_context(|| format!("slow_unlocked overflowed at account {}", account))?;
         if amount > sl.unlocked { // Note: the validator may have transferred everything out, and the unlocked may not have changed
           let locked = amount - sl.unlocked;
-          acc.slow_locked = acc.slow_locked.checked_add(locked).unwrap();
+          acc.slow_locked = acc.slow_locked.checked_add(locked)
+            .with_context(|| format!("slow_locked overflowed at account {}", account))?;
           // if this is the special case of a validator account with slow locked balance
           if r.val_cfg.is_some() {
-            acc.validator = acc.validator.checked_add(amount).unwrap();
-            acc.validator_locked = acc.validator_locked.checked_add(locked).unwrap();
+            acc.validator = acc.validator.checked_add(amount)
+              .with_context(|| format!("validator overflowed at account {}", account))?;
+            acc.validator_locked = acc.validator_locked.checked_add(locked)
+              .with_context(|| format!("validator_locked overflowed at account {}", account))?;
           }
 
         }
@@ -51,11 +60,88 @@ fn inc_supply(mut acc: Supply, r: &LegacyRecovery, dd_wallet_list: &Vec<LegacyAd
 
 
     } else {
-      acc.normal = acc.normal.checked_add(amount).unwrap();
+      acc.normal = acc.normal.checked_add(amount)
+        .with_context(|| format!("normal overflowed at account {}", account))?;
     }
     Ok(acc)
 }
 
+/// Non-`balance` coin sources that `get_supply_struct`'s warning refers to:
+/// donor-directed wallet deposits, the infrastructure escrow balance, and
+/// the record-level `cumulative_deposits` value. Transaction-fee pools and
+/// any other fields of `LegacyRecovery` are not tallied. Tallied separately from `Supply` so reconciliation can name exactly
+/// which category drifted from the expected on-chain total.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EscrowSupply {
+  pub donor_directed_escrow: u64,
+  pub infra_escrow: u64,
+  pub cumulative_deposits: u64,
+}
+
+impl EscrowSupply {
+  pub fn total(&self) -> anyhow::Result<u64> {
+    self.donor_directed_escrow
+      .checked_add(self.infra_escrow)
+      .context("escrow total overflowed summing donor_directed_escrow + infra_escrow")?
+      .checked_add(self.cumulative_deposits)
+      .context("escrow total overflowed summing in cumulative_deposits")
+  }
+}
+
+fn inc_escrow(mut acc: EscrowSupply, r: &LegacyRecovery) -> anyhow::Result<EscrowSupply> {
+  let account = r.account.context("recovery record missing account address")?;
+
+  if let Some(dd) = &r.donor_directed_wallet {
+    acc.donor_directed_escrow = acc.donor_directed_escrow.checked_add(dd.cumulative_deposits)
+      .with_context(|| format!("donor_directed_escrow overflowed at account {}", account))?;
+  }
+  if let Some(infra) = &r.infra_escrow {
+    acc.infra_escrow = acc.infra_escrow.checked_add(infra.balance)
+      .with_context(|| format!("infra_escrow overflowed at account {}", account))?;
+  }
+  if let Some(cd) = &r.cumulative_deposits {
+    acc.cumulative_deposits = acc.cumulative_deposits.checked_add(cd.value)
+      .with_context(|| format!("cumulative_deposits overflowed at account {}", account))?;
+  }
+  Ok(acc)
+}
+
+/// iterate over the recovery file and sum the coin sources outside
+/// `account::balance` that `EscrowSupply` tracks: `donor_directed_wallet`
+/// cumulative deposits, `infra_escrow` balance, and `cumulative_deposits`
+/// value. Transaction-fee pools are not read.
+pub fn get_escrow_struct(rec: &Vec<LegacyRecovery>) -> anyhow::Result<EscrowSupply> {
+  rec.iter().try_fold(EscrowSupply::default(), inc_escrow)
+}
+
+/// Reconcile the migrated ledger against `expected_total`: sum every
+/// `account::balance` coin (`get_supply_struct`) plus every non-balance
+/// escrow source (`get_escrow_struct`), and fail closed if the grand total
+/// doesn't match what the caller expected on-chain.
+pub fn reconcile_supply(rec: &Vec<LegacyRecovery>, expected_total: u64) -> anyhow::Result<Supply> {
+  let supply = get_supply_struct(rec)?;
+  let escrow = get_escrow_struct(rec)?;
+
+  let reconstructed = supply.total.checked_add(escrow.total()?)
+    .context("supply reconciliation overflowed while summing balances and escrow")?;
+
+  if reconstructed != expected_total {
+    let delta = reconstructed as i128 - expected_total as i128;
+    bail!(
+      "supply reconciliation failed: reconstructed {} (balances: {}, donor_directed_escrow: {}, infra_escrow: {}, cumulative_deposits: {}) vs expected {}, delta {}",
+      reconstructed,
+      supply.total,
+      escrow.donor_directed_escrow,
+      escrow.infra_escrow,
+      escrow.cumulative_deposits,
+      expected_total,
+      delta,
+    );
+  }
+
+  Ok(supply)
+}
+
 /// iterate over the recovery file and get the sum of all balances.
 /// Note: this may not be the "total supply", since there may be coins in other structs beside an account::balance, e.g escrowed in contracts.
 pub fn get_supply_struct(rec: &Vec<LegacyRecovery>) -> anyhow::Result<Supply> {
@@ -99,4 +185,33 @@ fn test_get_struct() {
     let pct_val_locked = supply.validator_locked as f64 / supply.total as f64;
     dbg!(&pct_val_locked);
     assert!(supply.total == 2397436809784621);
+}
+
+#[test]
+fn test_reconcile_supply_fails_closed_on_mismatch() {
+    let p = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/sample_export_recovery.json");
+
+    let r = crate::parse_json::parse(p).unwrap();
+
+    // an expected total that does not match the reconstructed ledger should
+    // be rejected rather than silently accepted.
+    let err = reconcile_supply(&r, 1).unwrap_err();
+    assert!(err.to_string().contains("supply reconciliation failed"));
+}
+
+#[test]
+fn test_reconcile_supply_succeeds_on_matching_total() {
+    let p = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/sample_export_recovery.json");
+
+    let r = crate::parse_json::parse(p).unwrap();
+
+    // the fixture's known on-chain total, independently fixed the same way
+    // as test_get_struct's assertion above (this fixture carries no escrow
+    // balances, so the expected total matches supply.total unchanged).
+    let expected_total = 2397436809784621;
+
+    let supply = reconcile_supply(&r, expected_total).unwrap();
+    assert_eq!(supply.total, expected_total);
 }
\ No newline at end of file
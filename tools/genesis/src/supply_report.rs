@@ -0,0 +1,178 @@
+//! Structured, machine-readable (Display/JSON/CSV) output for supply audits.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::supply::{EscrowSupply, Supply};
+
+/// `Supply` plus the derived percentages auditors want, all relative to
+/// the reconstructed grand total (`supply.total + escrow.total()`).
+#[derive(Debug, Clone, Serialize)]
+pub struct SupplyReport {
+  pub supply: Supply,
+  pub escrow: EscrowSupply,
+  pub escrow_total: u64,
+  pub grand_total: u64,
+  pub pct_normal: f64,
+  pub pct_slow: f64,
+  pub pct_slow_locked: f64,
+  pub pct_donor_directed: f64,
+  pub pct_validator_locked: f64,
+  pub pct_escrow: f64,
+}
+
+impl SupplyReport {
+  pub fn new(supply: Supply, escrow: EscrowSupply) -> anyhow::Result<Self> {
+    let escrow_total = escrow.total()?;
+    let grand_total = supply
+      .total
+      .checked_add(escrow_total)
+      .context("supply report overflowed while summing balances and escrow")?;
+
+    let pct = |amount: u64| -> f64 {
+      if grand_total == 0 {
+        0.0
+      } else {
+        amount as f64 / grand_total as f64
+      }
+    };
+
+    Ok(Self {
+      pct_normal: pct(supply.normal),
+      pct_slow: pct(supply.slow_total),
+      pct_slow_locked: pct(supply.slow_locked),
+      pct_donor_directed: pct(supply.donor_directed),
+      pct_validator_locked: pct(supply.validator_locked),
+      pct_escrow: pct(escrow_total),
+      supply,
+      escrow,
+      escrow_total,
+      grand_total,
+    })
+  }
+}
+
+/// Selects how `render_supply_report` renders a `SupplyReport`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+  /// Human-readable, one field per line.
+  Display,
+  Json,
+  Csv,
+}
+
+/// Render `report` in `format` to `path`, or to stdout when `path` is
+/// `None`.
+pub fn render_supply_report(
+  report: &SupplyReport,
+  format: OutputFormat,
+  path: Option<&Path>,
+) -> anyhow::Result<()> {
+  let rendered = match format {
+    OutputFormat::Display => format_display(report),
+    OutputFormat::Json => serde_json::to_string_pretty(report)
+      .context("failed to serialize supply report to JSON")?,
+    OutputFormat::Csv => format_csv(report)?,
+  };
+
+  match path {
+    Some(p) => fs::write(p, rendered)
+      .with_context(|| format!("failed to write supply report to {}", p.display())),
+    None => {
+      println!("{}", rendered);
+      Ok(())
+    }
+  }
+}
+
+fn format_display(report: &SupplyReport) -> String {
+  format!(
+    "total: {}\nnormal: {} ({:.4}%)\nslow_total: {} ({:.4}%)\nslow_locked: {} ({:.4}%)\nslow_unlocked: {}\ndonor_directed: {} ({:.4}%)\nvalidator: {}\nvalidator_locked: {} ({:.4}%)\nescrow_total: {} ({:.4}%)\ngrand_total: {}",
+    report.supply.total,
+    report.supply.normal,
+    report.pct_normal * 100.0,
+    report.supply.slow_total,
+    report.pct_slow * 100.0,
+    report.supply.slow_locked,
+    report.pct_slow_locked * 100.0,
+    report.supply.slow_unlocked,
+    report.supply.donor_directed,
+    report.pct_donor_directed * 100.0,
+    report.supply.validator,
+    report.supply.validator_locked,
+    report.pct_validator_locked * 100.0,
+    report.escrow_total,
+    report.pct_escrow * 100.0,
+    report.grand_total,
+  )
+}
+
+fn format_csv(report: &SupplyReport) -> anyhow::Result<String> {
+  let mut wtr = csv::Writer::from_writer(vec![]);
+  wtr
+    .write_record([
+      "total",
+      "normal",
+      "slow_total",
+      "slow_locked",
+      "slow_unlocked",
+      "donor_directed",
+      "validator",
+      "validator_locked",
+      "escrow_total",
+      "grand_total",
+      "pct_normal",
+      "pct_slow",
+      "pct_slow_locked",
+      "pct_donor_directed",
+      "pct_validator_locked",
+      "pct_escrow",
+    ])
+    .context("failed to write CSV header for supply report")?;
+
+  wtr
+    .write_record(&[
+      report.supply.total.to_string(),
+      report.supply.normal.to_string(),
+      report.supply.slow_total.to_string(),
+      report.supply.slow_locked.to_string(),
+      report.supply.slow_unlocked.to_string(),
+      report.supply.donor_directed.to_string(),
+      report.supply.validator.to_string(),
+      report.supply.validator_locked.to_string(),
+      report.escrow_total.to_string(),
+      report.grand_total.to_string(),
+      report.pct_normal.to_string(),
+      report.pct_slow.to_string(),
+      report.pct_slow_locked.to_string(),
+      report.pct_donor_directed.to_string(),
+      report.pct_validator_locked.to_string(),
+      report.pct_escrow.to_string(),
+    ])
+    .context("failed to write CSV row for supply report")?;
+
+  let bytes = wtr
+    .into_inner()
+    .context("failed to flush CSV writer for supply report")?;
+  String::from_utf8(bytes).context("supply report CSV was not valid UTF-8")
+}
+
+#[test]
+fn test_report_formats_render() {
+  use std::path::PathBuf;
+
+  let p = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests/fixtures/sample_export_recovery.json");
+  let rec = crate::parse_json::parse(p).unwrap();
+
+  let supply = crate::supply::get_supply_struct(&rec).unwrap();
+  let escrow = crate::supply::get_escrow_struct(&rec).unwrap();
+  let report = SupplyReport::new(supply, escrow).unwrap();
+
+  assert!(render_supply_report(&report, OutputFormat::Display, None).is_ok());
+  assert!(render_supply_report(&report, OutputFormat::Json, None).is_ok());
+  assert!(render_supply_report(&report, OutputFormat::Csv, None).is_ok());
+}
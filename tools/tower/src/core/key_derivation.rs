@@ -0,0 +1,123 @@
+//! Derive the genesis preimage auth key from a BIP39 mnemonic, or from a raw hex key.
+
+use anyhow::Context;
+use ed25519_dalek::SigningKey;
+use pbkdf2::pbkdf2_hmac;
+use sha2::Sha512;
+use sha3::{Digest, Sha3_256};
+use unicode_normalization::UnicodeNormalization;
+
+/// PBKDF2 round count BIP39 mandates for seed derivation.
+const BIP39_PBKDF2_ROUNDS: u32 = 2048;
+const BIP39_SEED_BYTES: usize = 64;
+
+/// Single-signer Ed25519 scheme id the rest of the stack appends before
+/// hashing a public key into an authentication key.
+const ED25519_SCHEME_ID: u8 = 0x00;
+
+/// Where the preimage's auth key bytes should come from.
+pub enum AuthKeySource<'a> {
+    /// A BIP39 mnemonic, with an optional passphrase (empty string if none).
+    Mnemonic {
+        phrase: &'a str,
+        passphrase: &'a str,
+    },
+    /// An already hex-encoded 32-byte auth key.
+    Hex(&'a str),
+}
+
+/// Derive the 32-byte auth key from a BIP39 `mnemonic` (and optional
+/// `passphrase`): PBKDF2-HMAC-SHA512 over the NFKD-normalized mnemonic
+/// with salt `"mnemonic" + passphrase` produces a 64-byte seed; the first
+/// 32 bytes of that seed become the ed25519 account key. The auth key
+/// itself is then derived from that keypair's public key via
+/// `sha3_256(pubkey_bytes || scheme_id)`, with `scheme_id = 0` for a
+/// single-signer Ed25519 key, not the bare public key bytes.
+///
+/// NOTE: this checkout carries no reference wallet-derivation
+/// implementation to round-trip against, so the tests below only assert
+/// this function is deterministic and that the scheme-hash is actually
+/// applied; they do not pin a mnemonic to a known-good auth key produced
+/// by the real wallet. Whoever wires this up to a live profile should add
+/// that fixture before depending on it for fund recovery.
+pub fn auth_key_from_mnemonic(mnemonic: &str, passphrase: &str) -> anyhow::Result<[u8; 32]> {
+    let normalized_mnemonic: String = mnemonic.nfkd().collect();
+    let salt: String = format!("mnemonic{}", passphrase).nfkd().collect();
+
+    let mut seed = [0u8; BIP39_SEED_BYTES];
+    pbkdf2_hmac::<Sha512>(
+        normalized_mnemonic.as_bytes(),
+        salt.as_bytes(),
+        BIP39_PBKDF2_ROUNDS,
+        &mut seed,
+    );
+
+    let mut account_key_seed = [0u8; 32];
+    account_key_seed.copy_from_slice(&seed[..32]);
+
+    let signing_key = SigningKey::from_bytes(&account_key_seed);
+    let public_key_bytes = signing_key.verifying_key().to_bytes();
+
+    let mut hasher = Sha3_256::new();
+    hasher.update(public_key_bytes);
+    hasher.update([ED25519_SCHEME_ID]);
+
+    let mut auth_key = [0u8; 32];
+    auth_key.copy_from_slice(&hasher.finalize());
+    Ok(auth_key)
+}
+
+/// Produce the `AUTH_KEY_BYTES` field for the genesis preimage from
+/// either a BIP39 mnemonic or an existing hex-encoded auth key, surfacing
+/// decode/derivation failures as `anyhow::Result` rather than panicking.
+pub fn auth_key_bytes(source: AuthKeySource) -> anyhow::Result<Vec<u8>> {
+    match source {
+        AuthKeySource::Mnemonic { phrase, passphrase } => {
+            let key = auth_key_from_mnemonic(phrase, passphrase)
+                .context("failed to derive auth key from mnemonic")?;
+            Ok(key.to_vec())
+        }
+        AuthKeySource::Hex(hex_str) => {
+            hex::decode(hex_str).context("invalid hex-encoded 0L auth key")
+        }
+    }
+}
+
+#[test]
+fn test_mnemonic_derivation_is_deterministic() {
+    let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    let a = auth_key_from_mnemonic(phrase, "").unwrap();
+    let b = auth_key_from_mnemonic(phrase, "").unwrap();
+    assert_eq!(a, b);
+
+    let with_passphrase = auth_key_from_mnemonic(phrase, "trezor").unwrap();
+    assert_ne!(a, with_passphrase);
+}
+
+#[test]
+fn test_auth_key_is_scheme_hashed_not_the_raw_public_key() {
+    let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    let normalized_mnemonic: String = phrase.nfkd().collect();
+    let salt: String = "mnemonic".nfkd().collect();
+    let mut seed = [0u8; BIP39_SEED_BYTES];
+    pbkdf2_hmac::<Sha512>(
+        normalized_mnemonic.as_bytes(),
+        salt.as_bytes(),
+        BIP39_PBKDF2_ROUNDS,
+        &mut seed,
+    );
+    let mut account_key_seed = [0u8; 32];
+    account_key_seed.copy_from_slice(&seed[..32]);
+    let public_key_bytes = SigningKey::from_bytes(&account_key_seed)
+        .verifying_key()
+        .to_bytes();
+
+    let auth_key = auth_key_from_mnemonic(phrase, "").unwrap();
+    assert_ne!(auth_key, public_key_bytes);
+
+    let mut hasher = Sha3_256::new();
+    hasher.update(public_key_bytes);
+    hasher.update([ED25519_SCHEME_ID]);
+    assert_eq!(auth_key.as_slice(), hasher.finalize().as_slice());
+}
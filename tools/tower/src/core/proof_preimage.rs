@@ -1,14 +1,33 @@
 //! genesis preimage formatting.
 
 use byteorder::{LittleEndian, WriteBytesExt};
-use hex::decode;
 use libra_types::legacy_types::{
     app_cfg::AppCfg,
     block::{GENESIS_VDF_ITERATIONS, GENESIS_VDF_SECURITY_PARAM},
 };
 
-/// Format the config file data into a fixed byte structure for easy parsing in Move/other languages
-pub fn genesis_preimage(cfg: &AppCfg) -> anyhow::Result<Vec<u8>> {
+use crate::core::key_derivation::{auth_key_bytes, AuthKeySource};
+use crate::core::vdf_proof::Mode as VdfMode;
+
+/// Byte offset of the PIETRZAK/WESOLOWSKI mode byte within the genesis
+/// preimage, i.e. `AUTH_KEY_BYTES + CHAIN_ID_BYTES + DIFFICULTY_BYTES +
+/// SECURITY_BYTES`. Exposed so the VDF module can read the mode back out of
+/// a preimage without duplicating the layout.
+pub const GENESIS_VDF_MODE_OFFSET: usize = 64;
+
+/// Format the config file data into a fixed byte structure for easy parsing
+/// in Move/other languages. `auth_key_source` selects where the
+/// `AUTH_KEY_BYTES` field comes from: pass `Some(AuthKeySource::Mnemonic {
+/// .. })` to derive a recoverable account's auth key from a seed phrase, or
+/// `None` to fall back to `profile.auth_key`, as before. `vdf_mode` selects
+/// which proof flavor the embedded mode byte commits to; `None` defaults to
+/// `VdfMode::Wesolowski`, since (unlike Pietrzak) it verifies for any
+/// `GENESIS_VDF_ITERATIONS` value, not only powers of two.
+pub fn genesis_preimage(
+    cfg: &AppCfg,
+    auth_key_source: Option<AuthKeySource>,
+    vdf_mode: Option<VdfMode>,
+) -> anyhow::Result<Vec<u8>> {
     const AUTH_KEY_BYTES: usize = 32;
     const CHAIN_ID_BYTES: usize = 16;
     const DIFFICULTY_BYTES: usize = 8;
@@ -17,16 +36,24 @@ pub fn genesis_preimage(cfg: &AppCfg) -> anyhow::Result<Vec<u8>> {
     const LINK_TO_TOWER: usize = 64; // optional, hash of the last proof of an existing tower.
     const STATEMENT_BYTES: usize = 895; // remainder
 
+    // offset of the PIETRZAK/WESOLOWSKI mode byte within the preimage, for
+    // callers (e.g. the vdf module) that need to read it back out.
+    debug_assert_eq!(
+        GENESIS_VDF_MODE_OFFSET,
+        AUTH_KEY_BYTES + CHAIN_ID_BYTES + DIFFICULTY_BYTES + SECURITY_BYTES
+    );
+
     let mut preimage: Vec<u8> = vec![];
 
     // assume user has set default_profile_nickname
     let profile = cfg.get_profile(None)?;
 
     // AUTH_KEY_BYTES
-    let mut padded_key_bytes = match decode(profile.auth_key.clone().to_string()) {
-        Err(x) => panic!("Invalid 0L Auth Key: {}", x),
-        Ok(key_bytes) => padding(key_bytes, AUTH_KEY_BYTES),
+    let key_bytes = match auth_key_source {
+        Some(source) => auth_key_bytes(source)?,
+        None => auth_key_bytes(AuthKeySource::Hex(&profile.auth_key.clone().to_string()))?,
     };
+    let mut padded_key_bytes = padding(key_bytes, AUTH_KEY_BYTES);
 
     preimage.append(&mut padded_key_bytes);
 
@@ -53,8 +80,9 @@ pub fn genesis_preimage(cfg: &AppCfg) -> anyhow::Result<Vec<u8>> {
         .write_u64::<LittleEndian>(*GENESIS_VDF_SECURITY_PARAM)
         .unwrap();
 
-    // PIETRZAK
-    preimage.write_u8(1).unwrap();
+    // PIETRZAK/WESOLOWSKI mode byte
+    let mode = vdf_mode.unwrap_or(VdfMode::Wesolowski);
+    preimage.write_u8(mode.to_byte()).unwrap();
 
     // LINK_TO_TOWER
     // Note: V7: Deprecated
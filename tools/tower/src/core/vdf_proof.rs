@@ -0,0 +1,299 @@
+//! Verifiable delay function evaluation and proofs (Pietrzak, Wesolowski) over the genesis preimage.
+
+use anyhow::{bail, Context};
+use num_bigint::BigUint;
+use num_traits::One;
+use sha2::{Digest, Sha256, Sha512};
+
+use libra_types::legacy_types::block::GENESIS_VDF_ITERATIONS;
+
+use crate::core::proof_preimage::GENESIS_VDF_MODE_OFFSET;
+
+/// Which VDF proof flavor to produce or check, mirroring the mode byte
+/// embedded in the genesis preimage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Pietrzak,
+    Wesolowski,
+}
+
+impl Mode {
+    /// Read the mode byte the preimage already carries and map it to a
+    /// `Mode`.
+    pub fn from_preimage(preimage: &[u8]) -> anyhow::Result<Self> {
+        let byte = preimage
+            .get(GENESIS_VDF_MODE_OFFSET)
+            .context("preimage too short to contain a VDF mode byte")?;
+        Self::try_from(*byte)
+    }
+
+    /// The mode byte `genesis_preimage` embeds for this flavor.
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Mode::Pietrzak => 1,
+            Mode::Wesolowski => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Mode {
+    type Error = anyhow::Error;
+
+    fn try_from(byte: u8) -> anyhow::Result<Self> {
+        match byte {
+            1 => Ok(Mode::Pietrzak),
+            2 => Ok(Mode::Wesolowski),
+            other => bail!("unknown VDF mode byte: {}", other),
+        }
+    }
+}
+
+/// A VDF proof, in whichever flavor `Mode` selected at evaluation time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Proof {
+    /// Wesolowski's proof is a single group element `pi = x^q`.
+    Wesolowski { pi: BigUint },
+    /// Pietrzak's proof is the sequence of recursive halfway points `mu`.
+    Pietrzak { mus: Vec<BigUint> },
+}
+
+/// Evaluate the VDF over `preimage` and produce a proof in the flavor
+/// `mode` selects. The preimage's own mode byte is not read here;
+/// `Mode::from_preimage` returns the flavor that byte commits to.
+pub fn prove(preimage: &[u8], mode: Mode) -> anyhow::Result<Proof> {
+    let x = statement_from_preimage(preimage);
+    let t = *GENESIS_VDF_ITERATIONS;
+    let y = evaluate(&x, t);
+
+    match mode {
+        Mode::Wesolowski => Ok(Proof::Wesolowski {
+            pi: wesolowski_prove(&x, &y, t),
+        }),
+        Mode::Pietrzak => {
+            require_power_of_two(t)?;
+            Ok(Proof::Pietrzak {
+                mus: pietrzak_prove(&x, &y, t),
+            })
+        }
+    }
+}
+
+/// Check that `proof` attests to `y = x^(2^T) mod N` for the `x` derived
+/// from `preimage`, without redoing all `T` squarings.
+pub fn verify(preimage: &[u8], y: &BigUint, proof: &Proof, mode: Mode) -> anyhow::Result<bool> {
+    let x = statement_from_preimage(preimage);
+    let t = *GENESIS_VDF_ITERATIONS;
+
+    let ok = match (mode, proof) {
+        (Mode::Wesolowski, Proof::Wesolowski { pi }) => wesolowski_verify(&x, y, t, pi),
+        (Mode::Pietrzak, Proof::Pietrzak { mus }) => {
+            require_power_of_two(t)?;
+            pietrzak_verify(&x, y, t, mus)
+        }
+        _ => bail!("proof flavor does not match requested VDF mode"),
+    };
+    Ok(ok)
+}
+
+/// Pietrzak's recursive halving only preserves the identity `x'^(2^T') ==
+/// y'` at every level when `T` stays a power of two all the way down to 1;
+/// for any other `T` the "proof" it produces simply does not verify. Rather
+/// than attempt an unsound generic split, require `GENESIS_VDF_ITERATIONS`
+/// to already be a power of two (or be padded up to one before calling
+/// this module) and fail closed otherwise.
+fn require_power_of_two(t: u64) -> anyhow::Result<()> {
+    if t == 0 || (t & (t - 1)) != 0 {
+        bail!(
+            "Pietrzak VDF proofs require the iteration count to be a power of two, got {}",
+            t
+        );
+    }
+    Ok(())
+}
+
+/// Hash the preimage into an element of the RSA group to use as the VDF
+/// input `x`.
+fn statement_from_preimage(preimage: &[u8]) -> BigUint {
+    let digest = Sha512::digest(preimage);
+    BigUint::from_bytes_be(&digest) % modulus()
+}
+
+/// `y = x^(2^T) mod N`, computed by `T` sequential squarings. This is the
+/// slow, non-parallelizable step the VDF relies on.
+fn evaluate(x: &BigUint, t: u64) -> BigUint {
+    let n = modulus();
+    let mut y = x.clone();
+    for _ in 0..t {
+        y = (&y * &y) % &n;
+    }
+    y
+}
+
+// ---- Wesolowski ----
+
+fn wesolowski_prove(x: &BigUint, y: &BigUint, t: u64) -> BigUint {
+    let n = modulus();
+    let l = hash_to_prime(x, y, t);
+    let q = pow_two(t) / &l;
+    x.modpow(&q, &n)
+}
+
+fn wesolowski_verify(x: &BigUint, y: &BigUint, t: u64, pi: &BigUint) -> bool {
+    let n = modulus();
+    let l = hash_to_prime(x, y, t);
+    let r = pow_two(t) % &l;
+    let lhs = (pi.modpow(&l, &n) * x.modpow(&r, &n)) % &n;
+    &lhs == y
+}
+
+/// Fiat-Shamir challenge: hash `(x, y, T, counter)`, force the result odd,
+/// and re-hash with the next counter until the candidate is a probable
+/// prime.
+fn hash_to_prime(x: &BigUint, y: &BigUint, t: u64) -> BigUint {
+    let mut counter: u64 = 0;
+    loop {
+        let mut hasher = Sha256::new();
+        hasher.update(x.to_bytes_be());
+        hasher.update(y.to_bytes_be());
+        hasher.update(t.to_le_bytes());
+        hasher.update(counter.to_le_bytes());
+        let mut candidate = BigUint::from_bytes_be(&hasher.finalize());
+        candidate |= BigUint::one();
+        if is_probable_prime(&candidate) {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+// ---- Pietrzak ----
+
+/// Requires `t` to be a power of two (enforced by `require_power_of_two`
+/// at every public call site) so each level's `T' = T/2` exactly matches
+/// the exponent the next level's identity checks against.
+fn pietrzak_prove(x: &BigUint, y: &BigUint, t: u64) -> Vec<BigUint> {
+    let n = modulus();
+    let mut mus = vec![];
+    let mut x = x.clone();
+    let mut y = y.clone();
+    let mut t = t;
+
+    while t > 1 {
+        let half = t / 2;
+        let mu = x.modpow(&pow_two(half), &n);
+        let r = fiat_shamir_challenge(&x, &y, &mu);
+
+        x = (x.modpow(&r, &n) * &mu) % &n;
+        y = (mu.modpow(&r, &n) * &y) % &n;
+        mus.push(mu);
+        t = half;
+    }
+    mus
+}
+
+fn pietrzak_verify(x: &BigUint, y: &BigUint, t: u64, mus: &[BigUint]) -> bool {
+    let n = modulus();
+    let mut x = x.clone();
+    let mut y = y.clone();
+    let mut t = t;
+
+    for mu in mus {
+        if t <= 1 {
+            return false;
+        }
+        let half = t / 2;
+        let r = fiat_shamir_challenge(&x, &y, mu);
+
+        x = (x.modpow(&r, &n) * mu) % &n;
+        y = (mu.modpow(&r, &n) * &y) % &n;
+        t = half;
+    }
+
+    t == 1 && (&x * &x) % &n == y
+}
+
+fn fiat_shamir_challenge(x: &BigUint, y: &BigUint, mu: &BigUint) -> BigUint {
+    let mut hasher = Sha256::new();
+    hasher.update(x.to_bytes_be());
+    hasher.update(y.to_bytes_be());
+    hasher.update(mu.to_bytes_be());
+    BigUint::from_bytes_be(&hasher.finalize())
+}
+
+// ---- group of unknown order ----
+
+/// 2048-bit odd group modulus, generated as a "nothing up my sleeve" number
+/// by hashing a fixed seed, so no party chose it with a known
+/// factorization. It is not built as a product of two primes like an RSA
+/// modulus, and any small factors it has are not removed.
+fn modulus() -> BigUint {
+    const SEED: &[u8] = b"libra-framework/genesis-vdf/rsa-modulus/v1";
+    const BYTES: usize = 256; // 2048 bits
+
+    let mut out = Vec::with_capacity(BYTES);
+    let mut counter: u32 = 0;
+    while out.len() < BYTES {
+        let mut hasher = Sha512::new();
+        hasher.update(SEED);
+        hasher.update(counter.to_le_bytes());
+        out.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    out.truncate(BYTES);
+    out[0] |= 0x80; // full bit length
+    let last = out.len() - 1;
+    out[last] |= 1; // odd
+
+    BigUint::from_bytes_be(&out)
+}
+
+fn pow_two(exp: u64) -> BigUint {
+    BigUint::from(2u8).pow(exp as u32)
+}
+
+/// Fermat primality test, sufficient here since `l` only needs to be a
+/// probable prime for soundness of the Fiat-Shamir challenge, not a
+/// certified one.
+fn is_probable_prime(candidate: &BigUint) -> bool {
+    if *candidate < BigUint::from(2u8) {
+        return false;
+    }
+    let base = BigUint::from(2u8);
+    base.modpow(&(candidate - BigUint::one()), candidate) == BigUint::one()
+}
+
+#[test]
+fn test_pietrzak_round_trip() {
+    let x = BigUint::from(7u32) % modulus();
+    let t = 8u64;
+    let y = evaluate(&x, t);
+    let mus = pietrzak_prove(&x, &y, t);
+    assert!(pietrzak_verify(&x, &y, t, &mus));
+}
+
+#[test]
+fn test_pietrzak_rejects_non_power_of_two_iterations() {
+    assert!(require_power_of_two(8).is_ok());
+    assert!(require_power_of_two(11).is_err());
+    assert!(require_power_of_two(0).is_err());
+}
+
+#[test]
+fn test_mode_round_trips_through_preimage_byte() {
+    let mut preimage = vec![0u8; GENESIS_VDF_MODE_OFFSET + 1];
+
+    preimage[GENESIS_VDF_MODE_OFFSET] = Mode::Pietrzak.to_byte();
+    assert_eq!(Mode::from_preimage(&preimage).unwrap(), Mode::Pietrzak);
+
+    preimage[GENESIS_VDF_MODE_OFFSET] = Mode::Wesolowski.to_byte();
+    assert_eq!(Mode::from_preimage(&preimage).unwrap(), Mode::Wesolowski);
+}
+
+#[test]
+fn test_wesolowski_round_trip() {
+    let x = BigUint::from(7u32) % modulus();
+    let t = 8u64;
+    let y = evaluate(&x, t);
+    let pi = wesolowski_prove(&x, &y, t);
+    assert!(wesolowski_verify(&x, &y, t, &pi));
+}